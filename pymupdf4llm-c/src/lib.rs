@@ -1,7 +1,9 @@
 use libc::c_void;
 use serde::Deserialize; // Required for the custom struct
+use std::collections::BTreeMap;
 use std::ffi::{CStr, CString, NulError};
 use std::fmt;
+use std::fmt::Write as _;
 use std::fs;
 use std::io;
 use std::os::raw::c_char;
@@ -11,7 +13,21 @@ use std::str::Utf8Error;
 // --- FFI to C library ---
 unsafe extern "C" {
     fn pdf_to_json(pdf_path: *const c_char, output_file: *const c_char) -> i32;
+    fn pdf_to_json_encrypted(
+        pdf_path: *const c_char,
+        output_file: *const c_char,
+        password: *const c_char,
+    ) -> i32;
     fn page_to_json_string(pdf_path: *const c_char, page_number: i32) -> *mut c_char;
+    // On a NULL return, `status_out` receives the native error code so callers
+    // can distinguish PasswordRequired / AuthenticationFailed / CorruptDocument.
+    fn page_to_json_string_encrypted(
+        pdf_path: *const c_char,
+        page_number: i32,
+        password: *const c_char,
+        status_out: *mut i32,
+    ) -> *mut c_char;
+    fn pdf_page_count(pdf_path: *const c_char) -> i32;
     fn free(ptr: *mut c_void);
 }
 
@@ -26,8 +42,23 @@ pub enum PdfError {
     NullResult,
     MissingInput(PathBuf),
     PageNumberOverflow,
+    /// The document is encrypted and no password was supplied.
+    PasswordRequired,
+    /// A password was supplied but it did not unlock the document.
+    AuthenticationFailed,
+    /// The document uses an encryption scheme this build cannot handle.
+    EncryptedUnsupported,
+    /// The document (or a specific page) is damaged and cannot be parsed.
+    CorruptDocument { page: Option<i32> },
 }
 
+// C return codes shared with the native extractor. Kept in sync with the
+// `tomd` library's error table so distinct failures map to distinct variants.
+const C_ERR_PASSWORD_REQUIRED: i32 = 2;
+const C_ERR_AUTHENTICATION_FAILED: i32 = 3;
+const C_ERR_ENCRYPTED_UNSUPPORTED: i32 = 4;
+const C_ERR_CORRUPT_DOCUMENT: i32 = 5;
+
 impl From<NulError> for PdfError {
     fn from(err: NulError) -> Self {
         PdfError::Nul(err)
@@ -63,6 +94,13 @@ impl fmt::Display for PdfError {
             PdfError::Json(err) => write!(f, "Failed to parse JSON output: {}", err),
             PdfError::NullResult => write!(f, "C extractor returned NULL"),
             PdfError::MissingInput(path) => write!(f, "Input PDF not found: {}", path.display()),
+            PdfError::PasswordRequired => write!(f, "Document is encrypted and requires a password"),
+            PdfError::AuthenticationFailed => write!(f, "Supplied password did not unlock the document"),
+            PdfError::EncryptedUnsupported => write!(f, "Document uses an unsupported encryption scheme"),
+            PdfError::CorruptDocument { page: Some(page) } => {
+                write!(f, "Document is corrupt at page {}", page)
+            }
+            PdfError::CorruptDocument { page: None } => write!(f, "Document is corrupt"),
         }
     }
 }
@@ -140,9 +178,9 @@ where
 {
     use serde::de::Error;
     use serde_json::Value;
-    
+
     let value = Value::deserialize(deserializer)?;
-    
+
     match value {
         Value::Array(arr) if arr.len() == 4 => {
             Ok(BBox {
@@ -172,6 +210,62 @@ where
     }
 }
 
+/// Whether `scale` differs from the identity (native 72-dpi points).
+fn scale_is_active(scale: f64) -> bool {
+    (scale - 1.0).abs() > f64::EPSILON
+}
+
+/// Recursively multiply every `bbox` field found in `value` by `scale`, so a
+/// document's coordinates can be mapped into a target DPI / pixel space. This is
+/// the single place coordinate scaling happens, mirroring MuPDF's stext
+/// `as_json(scale)`; nested table `rows`/`cells` bboxes are scaled too.
+fn scale_json_bboxes(value: &mut serde_json::Value, scale: f64) {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key == "bbox" {
+                    scale_bbox_value(child, scale);
+                } else {
+                    scale_json_bboxes(child, scale);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                scale_json_bboxes(item, scale);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Scale a single bbox value in either `[x0, y0, x1, y1]` or `{x0, y0, x1, y1}` form.
+fn scale_bbox_value(value: &mut serde_json::Value, scale: f64) {
+    use serde_json::Value;
+
+    match value {
+        Value::Array(coords) => {
+            for coord in coords.iter_mut() {
+                if let Some(n) = coord.as_f64() {
+                    *coord = Value::from(n * scale);
+                }
+            }
+        }
+        Value::Object(map) => {
+            for key in ["x0", "y0", "x1", "y1"] {
+                if let Some(coord) = map.get_mut(key)
+                    && let Some(n) = coord.as_f64()
+                {
+                    *coord = Value::from(n * scale);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 // --- Public API ---
 
 /// Extract all pages and parse the JSON payload into strongly typed Blocks.
@@ -183,14 +277,137 @@ where
     P: AsRef<Path>,
     Q: AsRef<Path>,
 {
-    let json_file = to_json(pdf_path, output_file)?;
+    to_json_collect_with_password(pdf_path, output_file, None, 1.0)
+}
+
+/// Like [`to_json_collect`], but supplies a password for an encrypted document
+/// and a coordinate `scale` applied to every `BBox` (see [`extract_page_json_scaled`]).
+pub fn to_json_collect_with_password<P, Q>(
+    pdf_path: P,
+    output_file: Option<Q>,
+    password: Option<&str>,
+    scale: f64,
+) -> Result<Vec<Block>, PdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    // `to_json_with_password` already scales the written file, so the contents
+    // are the single scaled source of truth — no further multiply here.
+    let json_file = to_json_with_password(pdf_path, output_file, password, scale)?;
     let contents = fs::read_to_string(&json_file)?;
     let blocks: Vec<Block> = serde_json::from_str(&contents)?;
     Ok(blocks)
 }
 
+/// Outcome of a parallel page-range extraction.
+///
+/// Holds the successfully extracted blocks (merged in page order) alongside the
+/// pages that could not be extracted, so a bad page reports per page instead of
+/// aborting the whole batch.
+#[derive(Debug)]
+pub struct RangeExtraction {
+    /// Successfully extracted blocks, merged in page order.
+    pub blocks: Vec<Block>,
+    /// Pages that failed, each paired with the reason it was dropped.
+    pub failures: Vec<(usize, PdfError)>,
+}
+
+/// Extract a contiguous page range in parallel and merge the `Block`s in page
+/// order.
+///
+/// The requested range is first clamped to the document's real page count (via
+/// `pdf_page_count`) so a large or sentinel `end_page` never over-allocates or
+/// spawns FFI calls for nonexistent pages; any out-of-document tail is reported
+/// once in [`RangeExtraction::failures`]. Pages `start_page..=end_page` are then
+/// fanned across a pool of worker threads, each driving the `page_to_json_string`
+/// FFI and freeing its own C string. A page that fails (NULL result or
+/// unparseable JSON) is recorded per page rather than aborting the batch, so
+/// extracting e.g. pages 50–120 of a large report yields a bounded,
+/// page-ordered result plus a per-page failure list. The merged JSON is also
+/// written to `output_file`.
+pub fn to_json_range<P, Q>(
+    pdf_path: P,
+    start_page: usize,
+    end_page: usize,
+    output_file: Q,
+) -> Result<RangeExtraction, PdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let pdf_path = pdf_path.as_ref();
+    if !pdf_path.exists() {
+        return Err(PdfError::MissingInput(pdf_path.to_path_buf()));
+    }
+
+    let pdf_c = path_to_cstring(pdf_path)?;
+
+    let total = unsafe { pdf_page_count(pdf_c.as_ptr()) };
+    if total < 0 {
+        return Err(map_c_error(total, None));
+    }
+    let page_count = total as usize;
+
+    let mut failures: Vec<(usize, PdfError)> = Vec::new();
+
+    // Clamp the request to what the document actually contains, reporting any
+    // missing tail once instead of one failure per nonexistent index.
+    let results = if page_count == 0 || start_page >= page_count {
+        failures.push((start_page, PdfError::PageNumberOverflow));
+        Vec::new()
+    } else {
+        let last_page = page_count - 1;
+        if end_page > last_page {
+            failures.push((page_count, PdfError::PageNumberOverflow));
+        }
+        extract_page_range_parallel(&pdf_c, start_page, end_page.min(last_page))
+    };
+
+    // Merge the successful pages in page order; record the rest per page.
+    let mut merged: Vec<serde_json::Value> = Vec::new();
+    let mut blocks: Vec<Block> = Vec::new();
+    for (page, result) in results {
+        match result.and_then(|json| {
+            let values = serde_json::from_str::<Vec<serde_json::Value>>(&json)?;
+            let page_blocks = serde_json::from_str::<Vec<Block>>(&json)?;
+            Ok((values, page_blocks))
+        }) {
+            Ok((values, page_blocks)) => {
+                merged.extend(values);
+                blocks.extend(page_blocks);
+            }
+            Err(err) => failures.push((page, err)),
+        }
+    }
+
+    let target_file = output_file.as_ref();
+    if let Some(parent) = target_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(target_file, serde_json::to_string(&merged)?)?;
+
+    Ok(RangeExtraction { blocks, failures })
+}
+
 /// Extract an entire PDF into a single merged JSON file.
 pub fn to_json<P, Q>(pdf_path: P, output_file: Option<Q>) -> Result<PathBuf, PdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    to_json_with_password(pdf_path, output_file, None, 1.0)
+}
+
+/// Like [`to_json`], but supplies a password for an encrypted document and a
+/// coordinate `scale` applied to every `BBox` in the written JSON (see
+/// [`extract_page_json_scaled`]).
+pub fn to_json_with_password<P, Q>(
+    pdf_path: P,
+    output_file: Option<Q>,
+    password: Option<&str>,
+    scale: f64,
+) -> Result<PathBuf, PdfError>
 where
     P: AsRef<Path>,
     Q: AsRef<Path>,
@@ -201,17 +418,54 @@ where
     }
 
     let target_file = resolve_output_file(pdf_path, output_file);
-    
+
     if let Some(parent) = target_file.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    convert_document(pdf_path, &target_file)?;
+    convert_document(pdf_path, &target_file, password)?;
+
+    // Scale in Rust (the single source of truth) by rewriting the file once.
+    if scale_is_active(scale) {
+        let contents = fs::read_to_string(&target_file)?;
+        let mut value: serde_json::Value = serde_json::from_str(&contents)?;
+        scale_json_bboxes(&mut value, scale);
+        fs::write(&target_file, serde_json::to_string(&value)?)?;
+    }
+
     Ok(target_file)
 }
 
 /// Extract a single page into an in-memory JSON string.
 pub fn extract_page_json<P>(pdf_path: P, page_number: usize) -> Result<String, PdfError>
+where
+    P: AsRef<Path>,
+{
+    extract_page_json_with_password(pdf_path, page_number, None, 1.0)
+}
+
+/// Like [`extract_page_json`], but renders bbox coordinates at `scale` instead
+/// of the default 72-dpi points, so downstream consumers can overlay block
+/// boxes onto page images rasterized at the same resolution.
+pub fn extract_page_json_scaled<P>(
+    pdf_path: P,
+    page_number: usize,
+    scale: f64,
+) -> Result<String, PdfError>
+where
+    P: AsRef<Path>,
+{
+    extract_page_json_with_password(pdf_path, page_number, None, scale)
+}
+
+/// Like [`extract_page_json`], but supplies a password for an encrypted document
+/// and a coordinate `scale` applied to every `BBox` in the returned JSON.
+pub fn extract_page_json_with_password<P>(
+    pdf_path: P,
+    page_number: usize,
+    password: Option<&str>,
+    scale: f64,
+) -> Result<String, PdfError>
 where
     P: AsRef<Path>,
 {
@@ -226,10 +480,29 @@ where
 
     let pdf_c = path_to_cstring(pdf_path)?;
 
-    let ptr = unsafe { page_to_json_string(pdf_c.as_ptr(), page_number as i32) };
+    // Encrypted extraction reports a granular status through an out-param; the
+    // plain path has no such channel, so a NULL there is reported as `NullResult`.
+    let mut status: i32 = 0;
+    let ptr = match password {
+        Some(password) => {
+            let password_c = CString::new(password)?;
+            unsafe {
+                page_to_json_string_encrypted(
+                    pdf_c.as_ptr(),
+                    page_number as i32,
+                    password_c.as_ptr(),
+                    &mut status,
+                )
+            }
+        }
+        None => unsafe { page_to_json_string(pdf_c.as_ptr(), page_number as i32) },
+    };
 
     if ptr.is_null() {
-        return Err(PdfError::NullResult);
+        return Err(match password {
+            Some(_) if status != 0 => map_c_error(status, Some(page_number as i32)),
+            _ => PdfError::NullResult,
+        });
     }
 
     let json = unsafe {
@@ -239,23 +512,217 @@ where
         owned
     };
 
+    // Scale in Rust (the single source of truth) to stay consistent with the
+    // document path; identical `scale` values yield identically-scaled bboxes.
+    if scale_is_active(scale) {
+        let mut value: serde_json::Value = serde_json::from_str(&json)?;
+        scale_json_bboxes(&mut value, scale);
+        return Ok(serde_json::to_string(&value)?);
+    }
+
     Ok(json)
 }
 
+/// Extract a PDF and reassemble its `Block`s into LLM-ready Markdown.
+///
+/// Built on top of [`to_json_collect`]: blocks are grouped by page, sorted back
+/// into reading order, headings are inferred from font sizes and tables rendered
+/// as GFM pipe tables. See [`to_markdown`] to write the result straight to a file.
+pub fn to_markdown_string<P>(pdf_path: P) -> Result<String, PdfError>
+where
+    P: AsRef<Path>,
+{
+    let blocks = to_json_collect(pdf_path, None::<&Path>)?;
+    Ok(blocks_to_markdown(&blocks))
+}
+
+/// Extract a PDF to Markdown and write it to `output_file`, returning its path.
+pub fn to_markdown<P, Q>(pdf_path: P, output_file: Q) -> Result<PathBuf, PdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let markdown = to_markdown_string(pdf_path)?;
+    let target_file = output_file.as_ref().to_path_buf();
+
+    if let Some(parent) = target_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&target_file, markdown)?;
+    Ok(target_file)
+}
+
+/// Lazily iterate a PDF one page at a time without materializing the whole
+/// document.
+///
+/// Returns a [`PageBlocks`] iterator whose `next()` extracts and parses a single
+/// page via the FFI, yielding `Result<Vec<Block>, PdfError>`. Memory stays
+/// constant in the page count, which matters for thousand-page PDFs feeding a
+/// chunked LLM pipeline.
+pub fn iter_pages<P>(pdf_path: P) -> Result<PageBlocks, PdfError>
+where
+    P: AsRef<Path>,
+{
+    let pdf_path = pdf_path.as_ref();
+    if !pdf_path.exists() {
+        return Err(PdfError::MissingInput(pdf_path.to_path_buf()));
+    }
+
+    let pdf_c = path_to_cstring(pdf_path)?;
+    let total = unsafe { pdf_page_count(pdf_c.as_ptr()) };
+    if total < 0 {
+        return Err(PdfError::CError(total));
+    }
+
+    Ok(PageBlocks {
+        pdf_c,
+        current: 0,
+        total,
+    })
+}
+
+/// Streaming, page-at-a-time iterator over a PDF's `Block`s.
+///
+/// Created by [`iter_pages`]. Each call to [`Iterator::next`] drives the
+/// `page_to_json_string` FFI for the current page, parses its JSON into
+/// `Vec<Block>` and frees the returned C pointer before advancing. Iteration
+/// ends once a page yields NULL or the page count is reached.
+pub struct PageBlocks {
+    pdf_c: CString,
+    current: i32,
+    total: i32,
+}
+
+impl Iterator for PageBlocks {
+    type Item = Result<Vec<Block>, PdfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current >= self.total {
+            return None;
+        }
+
+        let ptr = unsafe { page_to_json_string(self.pdf_c.as_ptr(), self.current) };
+        if ptr.is_null() {
+            // A NULL page ends iteration early (e.g. a truncated document).
+            self.current = self.total;
+            return None;
+        }
+
+        let parsed = unsafe {
+            let json = CStr::from_ptr(ptr).to_str().map(str::to_owned);
+            free(ptr as *mut c_void);
+            json
+        };
+
+        self.current += 1;
+
+        let result = parsed
+            .map_err(PdfError::from)
+            .and_then(|json| serde_json::from_str::<Vec<Block>>(&json).map_err(PdfError::from));
+        Some(result)
+    }
+}
+
 // --- Internal Helpers ---
 
-fn convert_document(pdf_path: &Path, target_file: &Path) -> Result<(), PdfError> {
+fn convert_document(
+    pdf_path: &Path,
+    target_file: &Path,
+    password: Option<&str>,
+) -> Result<(), PdfError> {
     let pdf_c = path_to_cstring(pdf_path)?;
     let file_c = path_to_cstring(target_file)?;
 
-    let ret = unsafe { pdf_to_json(pdf_c.as_ptr(), file_c.as_ptr()) };
+    let ret = match password {
+        Some(password) => {
+            let password_c = CString::new(password)?;
+            unsafe {
+                pdf_to_json_encrypted(pdf_c.as_ptr(), file_c.as_ptr(), password_c.as_ptr())
+            }
+        }
+        None => unsafe { pdf_to_json(pdf_c.as_ptr(), file_c.as_ptr()) },
+    };
     if ret != 0 {
-        return Err(PdfError::CError(ret));
+        return Err(map_c_error(ret, None));
     }
 
     Ok(())
 }
 
+/// Fan the per-page extraction across a pool of worker threads, returning a
+/// `(page, result)` pair for each page in `start..=end`, in page order.
+fn extract_page_range_parallel(
+    pdf_c: &CString,
+    start: usize,
+    end: usize,
+) -> Vec<(usize, Result<String, PdfError>)> {
+    if start > end {
+        return Vec::new();
+    }
+
+    let pages: Vec<usize> = (start..=end).collect();
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(pages.len());
+    let chunk_size = pages.len().div_ceil(workers);
+
+    // Each worker owns a contiguous page chunk and frees its own C strings
+    // (inside `extract_one_page`); concatenating the chunks in order preserves
+    // page order in the merged result.
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = pages
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&page| (page, extract_one_page(pdf_c, page)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(pages.len());
+        for handle in handles {
+            results.extend(handle.join().expect("extraction worker panicked"));
+        }
+        results
+    })
+}
+
+/// Extract a single page to its raw JSON string, freeing the C pointer before
+/// returning. Overflowing and NULL pages become per-page errors.
+fn extract_one_page(pdf_c: &CString, page: usize) -> Result<String, PdfError> {
+    if page > i32::MAX as usize {
+        return Err(PdfError::PageNumberOverflow);
+    }
+
+    let ptr = unsafe { page_to_json_string(pdf_c.as_ptr(), page as i32) };
+    if ptr.is_null() {
+        return Err(PdfError::NullResult);
+    }
+
+    let json = unsafe {
+        let json = CStr::from_ptr(ptr).to_str().map(str::to_owned);
+        free(ptr as *mut c_void);
+        json
+    }?;
+    Ok(json)
+}
+
+/// Map a native return code to the most specific `PdfError` variant available.
+fn map_c_error(code: i32, page: Option<i32>) -> PdfError {
+    match code {
+        C_ERR_PASSWORD_REQUIRED => PdfError::PasswordRequired,
+        C_ERR_AUTHENTICATION_FAILED => PdfError::AuthenticationFailed,
+        C_ERR_ENCRYPTED_UNSUPPORTED => PdfError::EncryptedUnsupported,
+        C_ERR_CORRUPT_DOCUMENT => PdfError::CorruptDocument { page },
+        other => PdfError::CError(other),
+    }
+}
+
 fn resolve_output_file<P>(pdf_path: &Path, output_file: Option<P>) -> PathBuf
 where
     P: AsRef<Path>,
@@ -267,4 +734,279 @@ where
 
 fn default_output_file(pdf_path: &Path) -> PathBuf {
     pdf_path.with_extension("json")
+}
+
+// --- Markdown Reconstruction ---
+
+/// Width of the x0 bins used to recover column order on two-column layouts.
+const COLUMN_BIN_WIDTH: f64 = 50.0;
+
+/// Coarse column index for an `x0`, clustering nearby left edges together.
+fn column_bin(x0: f64) -> i64 {
+    (x0 / COLUMN_BIN_WIDTH).round() as i64
+}
+
+/// Whether a page is laid out in multiple columns: at least two distinct x0
+/// bins each holding two or more blocks. A lone centered title sits in its own
+/// bin but with a single block, so it never trips the detection.
+fn is_multi_column(blocks: &[&Block]) -> bool {
+    let mut counts: BTreeMap<i64, usize> = BTreeMap::new();
+    for block in blocks {
+        *counts.entry(column_bin(block.bbox.x0)).or_default() += 1;
+    }
+    counts.values().filter(|&&count| count >= 2).count() >= 2
+}
+
+/// Reassemble parsed `Block`s into a single Markdown document.
+fn blocks_to_markdown(blocks: &[Block]) -> String {
+    let heading_levels = heading_level_map(blocks);
+    let body = body_font_size(blocks);
+
+    // Group by page so pages render in document order.
+    let mut pages: BTreeMap<i32, Vec<&Block>> = BTreeMap::new();
+    for block in blocks {
+        pages.entry(block.page_number).or_default().push(block);
+    }
+
+    let mut sections: Vec<String> = Vec::new();
+    for page_blocks in pages.values_mut() {
+        // Recover reading order: top-to-bottom then left-to-right. For genuine
+        // multi-column pages, order by column first so the columns don't
+        // interleave; single-column pages keep y0 primary so centered or
+        // indented blocks (e.g. a title) stay in place.
+        if is_multi_column(page_blocks) {
+            page_blocks.sort_by(|a, b| {
+                column_bin(a.bbox.x0)
+                    .cmp(&column_bin(b.bbox.x0))
+                    .then(a.bbox.y0.total_cmp(&b.bbox.y0))
+                    .then(a.bbox.x0.total_cmp(&b.bbox.x0))
+            });
+        } else {
+            page_blocks.sort_by(|a, b| {
+                a.bbox.y0.total_cmp(&b.bbox.y0).then(a.bbox.x0.total_cmp(&b.bbox.x0))
+            });
+        }
+
+        for block in page_blocks {
+            match block.r#type.as_str() {
+                "table" => {
+                    if let Some(table) = render_table(block) {
+                        sections.push(table);
+                    }
+                }
+                _ => {
+                    let text = block.text.trim();
+                    if text.is_empty() {
+                        continue;
+                    }
+                    match heading_level_for(block, &heading_levels, body) {
+                        Some(level) => sections.push(format!("{} {}", "#".repeat(level), text)),
+                        None => sections.push(text.to_string()),
+                    }
+                }
+            }
+        }
+    }
+
+    sections.join("\n\n")
+}
+
+/// Map each distinct heading font size to a level, largest size = `#`.
+fn heading_level_map(blocks: &[Block]) -> BTreeMap<u64, usize> {
+    let body = body_font_size(blocks);
+
+    // Distinct sizes meaningfully larger than the body text.
+    let mut sizes: Vec<f64> = blocks
+        .iter()
+        .filter(|b| b.r#type == "text")
+        .map(|b| b.font_size)
+        .filter(|&size| size > body * 1.15)
+        .collect();
+    sizes.sort_by(|a, b| b.total_cmp(a));
+    sizes.dedup_by(|a, b| (*a - *b).abs() < f64::EPSILON);
+
+    sizes
+        .into_iter()
+        .take(6)
+        .enumerate()
+        .map(|(idx, size)| (size.to_bits(), idx + 1))
+        .collect()
+}
+
+/// Body font size is the median size across all text blocks.
+fn body_font_size(blocks: &[Block]) -> f64 {
+    let mut sizes: Vec<f64> = blocks
+        .iter()
+        .filter(|b| b.r#type == "text" && b.font_size > 0.0)
+        .map(|b| b.font_size)
+        .collect();
+    if sizes.is_empty() {
+        return 0.0;
+    }
+    sizes.sort_by(|a, b| a.total_cmp(b));
+    sizes[sizes.len() / 2]
+}
+
+/// Resolve the heading level for a text block. Bold acts only as a tiebreaker
+/// among heading-sized candidates: a larger-than-body bold block that fell just
+/// short of a heading bin is promoted to the deepest level, but ordinary
+/// body-sized bold emphasis stays a paragraph.
+fn heading_level_for(block: &Block, levels: &BTreeMap<u64, usize>, body: f64) -> Option<usize> {
+    if let Some(&level) = levels.get(&block.font_size.to_bits()) {
+        return Some(level);
+    }
+    if !levels.is_empty()
+        && block.font_weight.as_deref() == Some("bold")
+        && block.font_size > body
+    {
+        return levels.values().copied().max();
+    }
+    None
+}
+
+/// Render a `table` block as a GFM pipe table, escaping `|` inside cells.
+fn render_table(block: &Block) -> Option<String> {
+    let rows = block.rows.as_ref()?;
+    if rows.is_empty() {
+        return None;
+    }
+
+    let width = rows.iter().map(|r| r.cells.len()).max().unwrap_or(0);
+    if width == 0 {
+        return None;
+    }
+
+    let mut out = String::new();
+    for (idx, row) in rows.iter().enumerate() {
+        out.push('|');
+        for col in 0..width {
+            let cell = row.cells.get(col).map(|c| escape_cell(&c.text)).unwrap_or_default();
+            let _ = write!(out, " {} |", cell);
+        }
+        out.push('\n');
+
+        if idx == 0 {
+            out.push('|');
+            for _ in 0..width {
+                out.push_str(" --- |");
+            }
+            out.push('\n');
+        }
+    }
+
+    // Trim the trailing newline; sections are joined with blank lines.
+    Some(out.trim_end().to_string())
+}
+
+/// Escape a table cell for GFM: pipes are literal, newlines collapse to spaces.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(x0: f64, y0: f64) -> BBox {
+        BBox { x0, y0, x1: x0 + 100.0, y1: y0 + 10.0 }
+    }
+
+    fn text_block(text: &str, font_size: f64, bold: bool, bbox: BBox, page_number: i32) -> Block {
+        Block {
+            r#type: "text".to_string(),
+            text: text.to_string(),
+            bbox,
+            font_size,
+            font_weight: bold.then(|| "bold".to_string()),
+            page_number,
+            length: text.len(),
+            lines: None,
+            confidence: None,
+            row_count: None,
+            col_count: None,
+            cell_count: None,
+            rows: None,
+        }
+    }
+
+    fn cell(text: &str) -> TableCell {
+        TableCell { bbox: bbox(0.0, 0.0), text: text.to_string() }
+    }
+
+    #[test]
+    fn escape_cell_escapes_pipes_and_newlines() {
+        assert_eq!(escape_cell("a|b"), "a\\|b");
+        assert_eq!(escape_cell("line1\nline2"), "line1 line2");
+        assert_eq!(escape_cell("  spaced  "), "spaced");
+    }
+
+    #[test]
+    fn body_font_size_is_the_median() {
+        let blocks = vec![
+            text_block("a", 12.0, false, bbox(72.0, 10.0), 1),
+            text_block("b", 12.0, false, bbox(72.0, 20.0), 1),
+            text_block("c", 12.0, false, bbox(72.0, 30.0), 1),
+            text_block("title", 24.0, false, bbox(72.0, 0.0), 1),
+        ];
+        assert_eq!(body_font_size(&blocks), 12.0);
+    }
+
+    #[test]
+    fn heading_level_map_ranks_sizes_descending() {
+        let blocks = vec![
+            text_block("body", 12.0, false, bbox(72.0, 40.0), 1),
+            text_block("body", 12.0, false, bbox(72.0, 50.0), 1),
+            text_block("body", 12.0, false, bbox(72.0, 60.0), 1),
+            text_block("h1", 24.0, false, bbox(72.0, 0.0), 1),
+            text_block("h2", 18.0, false, bbox(72.0, 20.0), 1),
+        ];
+        let levels = heading_level_map(&blocks);
+        assert_eq!(levels.get(&24.0_f64.to_bits()), Some(&1));
+        assert_eq!(levels.get(&18.0_f64.to_bits()), Some(&2));
+        assert_eq!(levels.get(&12.0_f64.to_bits()), None);
+    }
+
+    #[test]
+    fn render_table_emits_gfm_with_header_and_escaping() {
+        let block = Block {
+            r#type: "table".to_string(),
+            rows: Some(vec![
+                TableRow { bbox: bbox(0.0, 0.0), cells: vec![cell("Name"), cell("Value")] },
+                TableRow { bbox: bbox(0.0, 10.0), cells: vec![cell("a|b"), cell("c")] },
+            ]),
+            ..text_block("", 0.0, false, bbox(0.0, 0.0), 1)
+        };
+        let rendered = render_table(&block).expect("table renders");
+        assert_eq!(
+            rendered,
+            "| Name | Value |\n| --- | --- |\n| a\\|b | c |"
+        );
+    }
+
+    #[test]
+    fn single_column_keeps_centered_title_first() {
+        // A centered title above left-aligned body text must lead the page.
+        let blocks = vec![
+            text_block("body one", 12.0, false, bbox(72.0, 100.0), 1),
+            text_block("body two", 12.0, false, bbox(72.0, 120.0), 1),
+            text_block("body three", 12.0, false, bbox(72.0, 140.0), 1),
+            text_block("Title", 24.0, false, bbox(220.0, 50.0), 1),
+        ];
+        let md = blocks_to_markdown(&blocks);
+        let first = md.lines().next().unwrap();
+        assert_eq!(first, "# Title");
+    }
+
+    #[test]
+    fn multi_column_orders_left_column_before_right() {
+        let blocks = vec![
+            text_block("left top", 12.0, false, bbox(72.0, 10.0), 1),
+            text_block("left bottom", 12.0, false, bbox(72.0, 40.0), 1),
+            text_block("right top", 12.0, false, bbox(320.0, 10.0), 1),
+            text_block("right bottom", 12.0, false, bbox(320.0, 40.0), 1),
+        ];
+        let md = blocks_to_markdown(&blocks);
+        let order: Vec<&str> = md.lines().filter(|l| !l.is_empty()).collect();
+        assert_eq!(order, vec!["left top", "left bottom", "right top", "right bottom"]);
+    }
 }
\ No newline at end of file